@@ -1,9 +1,363 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryInto;
+use std::io;
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 
 #[derive(Clone, Debug, PartialEq)]
 enum Type {
     Int(Option<i32>),
+    Float(Option<f64>),
+    Decimal(Option<BigDecimal>),
     Text(Option<String>),
+    DateTime(Option<DateTime<Utc>>),
+}
+
+/// Hashed consistently with the `Eq` impl below, so a join can index rows
+/// by key column in a `HashMap` instead of comparing every pair.
+impl std::hash::Hash for Type {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        match self {
+            Type::Int(v)      => { 0u8.hash(state); v.hash(state); }
+            Type::Float(v)    => { 1u8.hash(state); v.map(f64::to_bits).hash(state); }
+            Type::Decimal(v)  => { 2u8.hash(state); v.as_ref().map(|d| d.normalized().to_string()).hash(state); }
+            Type::Text(v)     => { 3u8.hash(state); v.hash(state); }
+            Type::DateTime(v) => { 4u8.hash(state); v.hash(state); }
+        }
+    }
+}
+
+/// A total order over `Type`, so group-key tuples can live in a
+/// `BTreeMap`. Values of the same variant compare by value (NULL first);
+/// `Float` uses `total_cmp` to give NaN a well-defined place. Mismatched
+/// variants fall back to a fixed variant rank so mixed-type keys still
+/// sort deterministically rather than panicking.
+impl Eq for Type {}
+
+impl PartialOrd for Type {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Type {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        fn variant_rank(t: &Type) -> u8 {
+            match t {
+                Type::Int(_)      => 0,
+                Type::Float(_)    => 1,
+                Type::Decimal(_)  => 2,
+                Type::Text(_)     => 3,
+                Type::DateTime(_) => 4,
+            }
+        }
+        match (self, other) {
+            (Type::Int(a), Type::Int(b))           => a.cmp(b),
+            (Type::Float(a), Type::Float(b))       => match (a, b) {
+                (Some(x), Some(y)) => x.total_cmp(y),
+                (None, None)       => std::cmp::Ordering::Equal,
+                (None, Some(_))    => std::cmp::Ordering::Less,
+                (Some(_), None)    => std::cmp::Ordering::Greater,
+            },
+            (Type::Decimal(a), Type::Decimal(b))   => a.cmp(b),
+            (Type::Text(a), Type::Text(b))         => a.cmp(b),
+            (Type::DateTime(a), Type::DateTime(b)) => a.cmp(b),
+            _ => variant_rank(self).cmp(&variant_rank(other)),
+        }
+    }
+}
+
+/// A NULL of the same variant as `t`, used when an aggregate's group has
+/// no non-null contributions.
+fn null_like(t: &Type) -> Type {
+    match t {
+        Type::Int(_)      => Type::Int(None),
+        Type::Float(_)    => Type::Float(None),
+        Type::Decimal(_)  => Type::Decimal(None),
+        Type::Text(_)     => Type::Text(None),
+        Type::DateTime(_) => Type::DateTime(None),
+    }
+}
+
+/// Aggregate functions usable with [`Table::group_by`].
+#[derive(Clone, Debug, PartialEq)]
+enum AggFn {
+    Count,
+    Sum,
+    Min,
+    Max,
+    Avg,
+}
+
+/// Sort direction usable with [`Table::order_by`].
+#[derive(Clone, Debug, PartialEq)]
+enum Order {
+    Asc,
+    Desc,
+}
+
+/// Compare two values with NULL sorting last, as `Type`'s `Ord` impl
+/// (used for `BTreeMap` group keys) sorts NULL first instead.
+fn compare_nullable(l: &Type, r: &Type) -> std::cmp::Ordering {
+    use std::cmp::Ordering::*;
+    match (l == &null_like(l), r == &null_like(r)) {
+        (true, true)   => Equal,
+        (true, false)  => Greater,
+        (false, true)  => Less,
+        (false, false) => l.cmp(r),
+    }
+}
+
+/// Join variants usable with [`Table::join`].
+#[derive(Clone, Debug, PartialEq)]
+enum JoinKind {
+    Inner,
+    Left,
+    Right,
+    Full,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum JoinError {
+    MissingKeyColumn(String),
+}
+
+impl std::fmt::Display for JoinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            JoinError::MissingKeyColumn(key) =>
+                write!(f, "key column \"{}\" not found in both tables", key),
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+fn agg_fn_name(f: &AggFn) -> &'static str {
+    match f {
+        AggFn::Count => "count",
+        AggFn::Sum   => "sum",
+        AggFn::Min   => "min",
+        AggFn::Max   => "max",
+        AggFn::Avg   => "avg",
+    }
+}
+
+/// AVG is derived as sum/count at finalization, keeping the division in
+/// the same numeric domain as the source column (`Decimal` stays exact,
+/// `Int` promotes to `Float` since an integer average is usually
+/// fractional).
+fn finalize_avg(sum: &Type, count: usize) -> Type {
+    match sum {
+        Type::Int(Some(s))     => Type::Float(Some(*s as f64 / count as f64)),
+        Type::Float(Some(s))   => Type::Float(Some(*s / count as f64)),
+        Type::Decimal(Some(s)) => Type::Decimal(Some(s.clone() / BigDecimal::from(count as i64))),
+        other => other.clone(),
+    }
+}
+
+/// Running count/sum/min/max for one aggregated column within a group.
+/// NULL values are skipped, matching SQL's COUNT(column)/SUM/AVG/MIN/MAX
+/// semantics (as opposed to COUNT(*), which this crate doesn't expose).
+#[derive(Clone, Debug, Default)]
+struct Accumulator {
+    count: usize,
+    sum:   Option<Type>,
+    min:   Option<Type>,
+    max:   Option<Type>,
+}
+
+impl Accumulator {
+    fn add(&mut self, val: &Type) {
+        if val == &null_like(val) {
+            return;
+        }
+        self.count += 1;
+        self.sum = Some(match &self.sum {
+            None => val.clone(),
+            Some(Type::Int(Some(a)))     => Type::Int(Some(a + match val { Type::Int(Some(b)) => *b, _ => 0 })),
+            Some(Type::Float(Some(a)))   => Type::Float(Some(a + match val { Type::Float(Some(b)) => *b, _ => 0.0 })),
+            Some(Type::Decimal(Some(a))) => match val {
+                Type::Decimal(Some(b)) => Type::Decimal(Some(a.clone() + b.clone())),
+                _ => Type::Decimal(Some(a.clone())),
+            },
+            Some(other) => other.clone(),
+        });
+        self.min = Some(match &self.min {
+            None => val.clone(),
+            Some(m) if Table::compare(&Op::Lt, val, m) => val.clone(),
+            Some(m) => m.clone(),
+        });
+        self.max = Some(match &self.max {
+            None => val.clone(),
+            Some(m) if Table::compare(&Op::Gt, val, m) => val.clone(),
+            Some(m) => m.clone(),
+        });
+    }
+}
+
+/// Parse a literal such as `'2020/01/01'` into a timestamp, for comparing
+/// a `DateTime` column against a string literal (e.g. `date < '2020/01/01'`).
+fn parse_datetime(s: &str) -> DateTime<Utc> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return dt.with_timezone(&Utc);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y/%m/%d") {
+        return Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap());
+    }
+    panic!("invalid datetime literal '{}'", s);
+}
+
+/// Order-preserving byte encoding for a single [`Type`] value, modeled on
+/// the cozo tuple format: integers are stored big-endian with the sign
+/// bit flipped so `memcmp` byte order equals numeric order (including
+/// negatives), and text is stored as UTF-8 followed by a NUL terminator
+/// (NUL sorts before every valid UTF-8 byte, so a prefix sorts first).
+/// A leading tag byte (0 = NULL, 1 = present) keeps NULL sorting before
+/// any value.
+fn encode_type(t: &Type) -> Vec<u8> {
+    match t {
+        Type::Int(None) | Type::Float(None) | Type::Decimal(None)
+            | Type::Text(None) | Type::DateTime(None) => vec![0u8],
+        Type::Int(Some(i)) => {
+            let mut buf = vec![1u8];
+            buf.extend_from_slice(&((*i as u32) ^ 0x8000_0000).to_be_bytes());
+            buf
+        }
+        Type::Float(Some(f)) => {
+            // IEEE-754 sign/mantissa flip: flip every bit for negatives,
+            // just the sign bit for non-negatives, so memcmp order matches
+            // numeric order.
+            let bits = f.to_bits();
+            let flipped = if bits & (1 << 63) != 0 { !bits } else { bits | (1 << 63) };
+            let mut buf = vec![1u8];
+            buf.extend_from_slice(&flipped.to_be_bytes());
+            buf
+        }
+        Type::Decimal(Some(d)) => {
+            // Not representable as a fixed-width int/float, so break it into
+            // sign + exponent + digit-string mantissa (0.digits * 10^exponent)
+            // and encode each piece the same sign-preserving way as above.
+            // Negative values flip every byte of exponent and mantissa so a
+            // larger magnitude (which sorts "bigger" in the positive scheme)
+            // sorts smaller, matching numeric order across the sign. Uses
+            // the value's stored scale as-is (not `normalized()`) so a
+            // round trip through `decode_type` keeps the original trailing
+            // zeros instead of collapsing e.g. `12.50` to `12.5`.
+            let (digits, scale) = d.as_bigint_and_exponent();
+            if digits.sign() == bigdecimal::num_bigint::Sign::NoSign {
+                vec![1u8, 1u8]
+            } else {
+                let negative = digits.sign() == bigdecimal::num_bigint::Sign::Minus;
+                let digits = digits.to_string();
+                let digits = digits.trim_start_matches('-');
+                let exponent = digits.len() as i64 - scale;
+                let mut exp_bytes = ((exponent as u64) ^ 0x8000_0000_0000_0000).to_be_bytes();
+                let mut mantissa = digits.as_bytes().to_vec();
+                mantissa.push(0u8);
+                if negative {
+                    for b in exp_bytes.iter_mut() { *b = !*b; }
+                    for b in mantissa.iter_mut() { *b = !*b; }
+                }
+                let mut buf = vec![1u8, if negative { 0u8 } else { 2u8 }];
+                buf.extend_from_slice(&exp_bytes);
+                buf.extend_from_slice(&mantissa);
+                buf
+            }
+        }
+        Type::DateTime(Some(dt)) => {
+            let nanos = dt.timestamp_nanos_opt().expect("datetime out of range");
+            let mut buf = vec![1u8];
+            buf.extend_from_slice(&((nanos as u64) ^ 0x8000_0000_0000_0000).to_be_bytes());
+            buf
+        }
+        Type::Text(Some(s)) => {
+            let mut buf = vec![1u8];
+            buf.extend_from_slice(s.as_bytes());
+            buf.push(0u8);
+            buf
+        }
+    }
+}
+
+fn decode_type(buf: &[u8], column: &Type) -> Type {
+    if buf[0] == 0 {
+        return match column {
+            Type::Int(_)      => Type::Int(None),
+            Type::Float(_)    => Type::Float(None),
+            Type::Decimal(_)  => Type::Decimal(None),
+            Type::Text(_)     => Type::Text(None),
+            Type::DateTime(_) => Type::DateTime(None),
+        };
+    }
+    match column {
+        Type::Int(_) => {
+            let bytes: [u8; 4] = buf[1..5].try_into().unwrap();
+            Type::Int(Some((u32::from_be_bytes(bytes) ^ 0x8000_0000) as i32))
+        }
+        Type::Float(_) => {
+            let bytes: [u8; 8] = buf[1..9].try_into().unwrap();
+            let flipped = u64::from_be_bytes(bytes);
+            let bits = if flipped & (1 << 63) != 0 { flipped & !(1 << 63) } else { !flipped };
+            Type::Float(Some(f64::from_bits(bits)))
+        }
+        Type::Decimal(_) => {
+            let sign_byte = buf[1];
+            if sign_byte == 1 {
+                return Type::Decimal(Some(BigDecimal::from(0)));
+            }
+            let negative = sign_byte == 0;
+            let mut exp_bytes: [u8; 8] = buf[2..10].try_into().unwrap();
+            if negative {
+                for b in exp_bytes.iter_mut() { *b = !*b; }
+            }
+            let exponent = (u64::from_be_bytes(exp_bytes) ^ 0x8000_0000_0000_0000) as i64;
+            let terminator = if negative { 0xFFu8 } else { 0u8 };
+            let end = buf[10..].iter().position(|&b| b == terminator).unwrap();
+            let mut mantissa = buf[10..10 + end].to_vec();
+            if negative {
+                for b in mantissa.iter_mut() { *b = !*b; }
+            }
+            let digits = String::from_utf8(mantissa).unwrap();
+            let scale = digits.len() as i64 - exponent;
+            let sign = if negative { "-" } else { "" };
+            let s = format!("{}{}e{}", sign, digits, -scale);
+            Type::Decimal(Some(s.parse().expect("invalid decimal in storage")))
+        }
+        Type::DateTime(_) => {
+            let bytes: [u8; 8] = buf[1..9].try_into().unwrap();
+            let nanos = (u64::from_be_bytes(bytes) ^ 0x8000_0000_0000_0000) as i64;
+            Type::DateTime(Some(Utc.timestamp_nanos(nanos)))
+        }
+        Type::Text(_) => {
+            let end = buf[1..].iter().position(|&b| b == 0).unwrap();
+            Type::Text(Some(String::from_utf8(buf[1..1 + end].to_vec()).unwrap()))
+        }
+    }
+}
+
+/// Serialize a row into a single key/value byte buffer: each column's
+/// encoded value is prefixed with its length so [`decode_row`] can walk
+/// the buffer back apart again.
+fn encode_row(row: &HashMap<String, Type>, order: &[String]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    for col in order {
+        let encoded = encode_type(&row[col]);
+        buf.extend_from_slice(&(encoded.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&encoded);
+    }
+    buf
+}
+
+fn decode_row(mut buf: &[u8], order: &[String], column: &HashMap<String, Type>) -> HashMap<String, Type> {
+    let mut row = HashMap::new();
+    for col in order {
+        let len = u32::from_be_bytes(buf[..4].try_into().unwrap()) as usize;
+        row.insert(col.clone(), decode_type(&buf[4..4 + len], &column[col]));
+        buf = &buf[4 + len..];
+    }
+    row
 }
 
 #[derive(Clone, Debug)]
@@ -22,6 +376,185 @@ enum LikeType {
     Str(String),  // other characters
 }
 
+/// Binary operators usable in a WHERE expression, ordered here from
+/// lowest to highest binding power: `OR` < `AND` < comparisons < `LIKE`.
+#[derive(Clone, Debug, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+    Like,
+}
+
+/// AST for a WHERE predicate, built by [`parse_expr`] and evaluated by
+/// [`Table::filter`].
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Column(String),
+    Literal(Type),
+    BinOp(Op, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    IntLit(i32),
+    DecimalLit(String),
+    StrLit(String),
+    Op(Op),
+    LParen,
+    RParen,
+}
+
+fn tokenize_expr(expr: &str) -> Vec<Token> {
+    let chars = expr.chars().collect::<Vec<char>>();
+    let mut tokens = vec![];
+    let mut idx = 0;
+    while idx < chars.len() {
+        match chars[idx] {
+            c if c.is_whitespace() => idx += 1,
+            '(' => { tokens.push(Token::LParen); idx += 1; }
+            ')' => { tokens.push(Token::RParen); idx += 1; }
+            '=' => { tokens.push(Token::Op(Op::Eq)); idx += 1; }
+            '<' if chars.get(idx + 1) == Some(&'>') => { tokens.push(Token::Op(Op::Ne)); idx += 2; }
+            '<' if chars.get(idx + 1) == Some(&'=') => { tokens.push(Token::Op(Op::Le)); idx += 2; }
+            '<' => { tokens.push(Token::Op(Op::Lt)); idx += 1; }
+            '>' if chars.get(idx + 1) == Some(&'=') => { tokens.push(Token::Op(Op::Ge)); idx += 2; }
+            '>' => { tokens.push(Token::Op(Op::Gt)); idx += 1; }
+            '\'' => {
+                idx += 1;
+                let mut s = String::new();
+                while idx < chars.len() && chars[idx] != '\'' {
+                    s.push(chars[idx]);
+                    idx += 1;
+                }
+                idx += 1; // closing quote
+                tokens.push(Token::StrLit(s));
+            }
+            // A `-` only starts a numeric literal when a digit follows;
+            // there's no subtraction operator, so a bare `-` elsewhere
+            // still falls through to the "unexpected character" panic.
+            c if c.is_ascii_digit() || (c == '-' && chars.get(idx + 1).is_some_and(|d| d.is_ascii_digit())) => {
+                let mut s = c.to_string();
+                idx += 1;
+                while idx < chars.len() && chars[idx].is_ascii_digit() {
+                    s.push(chars[idx]);
+                    idx += 1;
+                }
+                if chars.get(idx) == Some(&'.') {
+                    s.push('.');
+                    idx += 1;
+                    while idx < chars.len() && chars[idx].is_ascii_digit() {
+                        s.push(chars[idx]);
+                        idx += 1;
+                    }
+                    tokens.push(Token::DecimalLit(s));
+                } else {
+                    tokens.push(Token::IntLit(s.parse().unwrap()));
+                }
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut s = c.to_string();
+                idx += 1;
+                while idx < chars.len() && (chars[idx].is_alphanumeric() || chars[idx] == '_' || chars[idx] == '.') {
+                    s.push(chars[idx]);
+                    idx += 1;
+                }
+                match s.to_uppercase().as_str() {
+                    "AND"  => tokens.push(Token::Op(Op::And)),
+                    "OR"   => tokens.push(Token::Op(Op::Or)),
+                    "LIKE" => tokens.push(Token::Op(Op::Like)),
+                    _      => tokens.push(Token::Ident(s)),
+                }
+            }
+            c => panic!("unexpected character '{}' in WHERE expression", c),
+        }
+    }
+    tokens
+}
+
+/// Precedence-climbing parser over a flat token stream. Each `parse_*`
+/// level folds its operators left-to-right before handing control to the
+/// next tighter level; `(` resets precedence back to the loosest level.
+struct ExprParser<'a> {
+    tokens: &'a [Token],
+    pos:    usize,
+}
+
+impl<'a> ExprParser<'a> {
+    fn peek_op(&self) -> Option<&Op> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) => Some(op),
+            _ => None,
+        }
+    }
+
+    fn parse_or(&mut self) -> Expr {
+        let mut lhs = self.parse_and();
+        while self.peek_op() == Some(&Op::Or) {
+            self.pos += 1;
+            lhs = Expr::BinOp(Op::Or, Box::new(lhs), Box::new(self.parse_and()));
+        }
+        lhs
+    }
+
+    fn parse_and(&mut self) -> Expr {
+        let mut lhs = self.parse_comparison();
+        while self.peek_op() == Some(&Op::And) {
+            self.pos += 1;
+            lhs = Expr::BinOp(Op::And, Box::new(lhs), Box::new(self.parse_comparison()));
+        }
+        lhs
+    }
+
+    fn parse_comparison(&mut self) -> Expr {
+        let mut lhs = self.parse_like();
+        while let Some(op @ (Op::Eq | Op::Ne | Op::Lt | Op::Le | Op::Gt | Op::Ge)) = self.peek_op() {
+            let op = op.clone();
+            self.pos += 1;
+            lhs = Expr::BinOp(op, Box::new(lhs), Box::new(self.parse_like()));
+        }
+        lhs
+    }
+
+    fn parse_like(&mut self) -> Expr {
+        let mut lhs = self.parse_primary();
+        while self.peek_op() == Some(&Op::Like) {
+            self.pos += 1;
+            lhs = Expr::BinOp(Op::Like, Box::new(lhs), Box::new(self.parse_primary()));
+        }
+        lhs
+    }
+
+    fn parse_primary(&mut self) -> Expr {
+        let token = self.tokens.get(self.pos).expect("unexpected end of WHERE expression");
+        self.pos += 1;
+        match token {
+            Token::LParen => {
+                let e = self.parse_or();
+                self.pos += 1; // closing ')'
+                e
+            }
+            Token::Ident(name)   => Expr::Column(name.clone()),
+            Token::IntLit(n)     => Expr::Literal(Type::Int(Some(*n))),
+            Token::DecimalLit(s) => Expr::Literal(Type::Decimal(Some(s.parse().expect("invalid decimal literal")))),
+            Token::StrLit(s)     => Expr::Literal(Type::Text(Some(s.clone()))),
+            t => panic!("unexpected token in WHERE expression: {:?}", t),
+        }
+    }
+}
+
+fn parse_expr(expr: &str) -> Expr {
+    let tokens = tokenize_expr(expr);
+    let mut parser = ExprParser { tokens: &tokens, pos: 0 };
+    parser.parse_or()
+}
+
 impl Table {
     fn new(name: &str, col: Vec<(&str, Type)>) -> Self {
         let mut order    = Vec::new();
@@ -47,11 +580,7 @@ impl Table {
                 if hashmap.get(&d.0.into()).is_some() {
                     panic!("Duplicate column name \"{}\"", d.0.into());
                 }
-                let len = match &d.1 {
-                    Type::Int(Some(i))  => i32_len(*i),
-                    Type::Text(Some(t)) => t.len(),
-                    _ => 4, // null
-                };
+                let len = value_len(&d.1);
                 if self.max_lens[&d.0.into()] < len {
                     self.max_lens.insert(d.0.into(), len);
                 }
@@ -77,71 +606,101 @@ impl Table {
         new_t
     }
 
-    fn less_than(&self, col: &str, num: i32) -> Table {
+    /// Filter rows by a boolean WHERE expression, e.g.
+    /// `"price >= 100 AND name LIKE '%ri%' OR id < 3"`. Comparisons on
+    /// `Int` use numeric order, on `Text` lexical order, NULL operands
+    /// make a comparison false, and `LIKE` reuses the `like`/`tokenize_like`
+    /// machinery. Generalizes the old `less_than`/`like` filters into one
+    /// composable WHERE clause.
+    fn filter(&self, expr: &str) -> Table {
+        let ast = parse_expr(expr);
         let mut new_t = self.clone();
         new_t.data.clear();
         for d in &self.data {
-            if let Type::Int(Some(n)) = d[col] {
-                if n < num {
-                    new_t.data.push(d.clone());
-                }
+            if Self::eval_bool(&ast, d) {
+                new_t.data.push(d.clone());
             }
         }
         new_t
     }
 
-    fn like(&self, col: &str, pattern: &str) -> Table {
-        fn tokenize(pattern: &str) -> Vec<LikeType> {
-            let mut tokens = vec![];
-            let pattern_chars = pattern.chars().collect::<Vec<char>>();
-            let mut idx = 0;
-            while idx < pattern_chars.len() {
-                match pattern_chars[idx] {
-                    '_' => {
-                        tokens.push(LikeType::Underscore);
-                        idx += 1;
-                    }
-                    '%' => {
-                        tokens.push(LikeType::Percent);
-                        idx += 1;
-                    }
-                     _  => {
-                         let mut s = pattern_chars[idx].to_string();
-                         idx += 1;
-                         while idx < pattern_chars.len() && pattern_chars[idx] != '%' && pattern_chars[idx] != '_' {
-                             s.push(pattern_chars[idx]);
-                             idx += 1;
-                         }
-                         tokens.push(LikeType::Str(s));
-                     }
-                }
+    fn eval_bool(expr: &Expr, row: &HashMap<String, Type>) -> bool {
+        matches!(Self::eval(expr, row), Type::Int(Some(1)))
+    }
+
+    fn eval(expr: &Expr, row: &HashMap<String, Type>) -> Type {
+        match expr {
+            Expr::Column(name) => row.get(name).cloned().unwrap_or(Type::Int(None)),
+            Expr::Literal(t)   => t.clone(),
+            Expr::BinOp(Op::And, l, r) =>
+                Type::Int(Some((Self::eval_bool(l, row) && Self::eval_bool(r, row)) as i32)),
+            Expr::BinOp(Op::Or, l, r) =>
+                Type::Int(Some((Self::eval_bool(l, row) || Self::eval_bool(r, row)) as i32)),
+            Expr::BinOp(Op::Like, l, r) => {
+                let (lv, rv) = (Self::eval(l, row), Self::eval(r, row));
+                let matched = match (&lv, &rv) {
+                    (Type::Text(Some(s)), Type::Text(Some(pattern))) => like(&tokenize_like(pattern), s),
+                    _ => false,
+                };
+                Type::Int(Some(matched as i32))
             }
-            tokens
-        }
-        let pattern = tokenize(pattern);
-        let mut new_t = self.clone();
-        new_t.data.clear();
-        for d in &self.data {
-            if let Type::Text(Some(s)) = &d[col] {
-                if like(&pattern, &s) {
-                    new_t.data.push(d.clone());
-                }
+            Expr::BinOp(op, l, r) => {
+                let (lv, rv) = (Self::eval(l, row), Self::eval(r, row));
+                Type::Int(Some(Self::compare(op, &lv, &rv) as i32))
             }
         }
-        new_t
     }
 
-    fn left_join(&self, other: &Table, key: &str) -> Table {
-        let mut new_t = self.clone();
+    fn compare(op: &Op, l: &Type, r: &Type) -> bool {
+        use std::cmp::Ordering::*;
+        let ord = match (l, r) {
+            (Type::Int(Some(a)), Type::Int(Some(b)))         => a.cmp(b),
+            (Type::Float(Some(a)), Type::Float(Some(b)))     => a.partial_cmp(b).expect("NaN comparison"),
+            (Type::Decimal(Some(a)), Type::Decimal(Some(b))) => a.cmp(b),
+            (Type::Decimal(Some(a)), Type::Int(Some(b)))     => a.cmp(&BigDecimal::from(*b)),
+            (Type::Int(Some(a)), Type::Decimal(Some(b)))     => BigDecimal::from(*a).cmp(b),
+            // Float has no exact BigDecimal/i32 counterpart, so coerce the
+            // other side through f64 instead (matches how the tokenizer
+            // already parses every bare numeric WHERE literal as Int or
+            // Decimal, never Float).
+            (Type::Float(Some(a)), Type::Int(Some(b)))       => a.partial_cmp(&(*b as f64)).expect("NaN comparison"),
+            (Type::Int(Some(a)), Type::Float(Some(b)))       => (*a as f64).partial_cmp(b).expect("NaN comparison"),
+            (Type::Float(Some(a)), Type::Decimal(Some(b)))   => a.partial_cmp(&b.to_f64().expect("Decimal out of f64 range")).expect("NaN comparison"),
+            (Type::Decimal(Some(a)), Type::Float(Some(b)))   => a.to_f64().expect("Decimal out of f64 range").partial_cmp(b).expect("NaN comparison"),
+            (Type::Text(Some(a)), Type::Text(Some(b)))       => a.cmp(b),
+            (Type::DateTime(Some(a)), Type::DateTime(Some(b))) => a.cmp(b),
+            // a DateTime column compared against a quoted string literal
+            (Type::DateTime(Some(a)), Type::Text(Some(b)))   => a.cmp(&parse_datetime(b)),
+            (Type::Text(Some(a)), Type::DateTime(Some(b)))   => parse_datetime(a).cmp(b),
+            _ => return false, // NULL operands, or incomparable types, make a comparison false
+        };
+        match op {
+            Op::Eq => ord == Equal,
+            Op::Ne => ord != Equal,
+            Op::Lt => ord == Less,
+            Op::Le => ord != Greater,
+            Op::Gt => ord == Greater,
+            Op::Ge => ord != Less,
+            _ => unreachable!("non-comparison operator reached compare()"),
+        }
+    }
 
-        if other.column.get(key).is_none() || new_t.column.get(key).is_none() {
-            // error msg
-            return new_t;
+    /// Join against `other` on `key`, per `kind`: `Inner` drops left rows
+    /// with no match, `Left` keeps them with right columns NULL, `Right`
+    /// is the mirror of `Left`, and `Full` keeps unmatched rows from both
+    /// sides. Indexes `other`'s key column into a `HashMap` first so
+    /// matching is O(n+m) instead of a nested scan.
+    fn join(&self, other: &Table, key: &str, kind: JoinKind) -> Result<Table, JoinError> {
+        if !self.column.contains_key(key) || !other.column.contains_key(key) {
+            return Err(JoinError::MissingKeyColumn(key.to_string()));
         }
 
+        let mut new_t = self.clone();
+        new_t.data.clear();
+
         let mut other_cols = Vec::new();
         for o in &other.order {
-            if new_t.column.get(o).is_none() {
+            if !new_t.column.contains_key(o) {
                 new_t.order.push(o.clone());
                 new_t.column.insert(o.clone(), other.column[o].clone());
                 new_t.max_lens.insert(o.clone(), other.max_lens[o]);
@@ -149,16 +708,209 @@ impl Table {
             }
         }
 
-        for d in &mut new_t.data {
-            for od in &other.data {
-                if d.get(key) == od.get(key) {
-                    for oc in &other_cols {
-                        d.insert(oc.to_owned(), od.get(oc).unwrap().clone());
+        let mut index: HashMap<Type, Vec<usize>> = HashMap::new();
+        for (i, od) in other.data.iter().enumerate() {
+            index.entry(od[key].clone()).or_default().push(i);
+        }
+        let mut matched_right = vec![false; other.data.len()];
+
+        for d in &self.data {
+            match index.get(&d[key]) {
+                Some(matches) => {
+                    for &i in matches {
+                        matched_right[i] = true;
+                        let mut row = d.clone();
+                        for oc in &other_cols {
+                            row.insert(oc.clone(), other.data[i][oc].clone());
+                        }
+                        new_t.data.push(row);
                     }
                 }
+                None if kind == JoinKind::Inner || kind == JoinKind::Right => {}
+                None => new_t.data.push(d.clone()),
+            }
+        }
+
+        if kind == JoinKind::Right || kind == JoinKind::Full {
+            for (i, od) in other.data.iter().enumerate() {
+                if matched_right[i] {
+                    continue;
+                }
+                let row = new_t.order.iter()
+                    .map(|c| {
+                        let val = if c == key || other_cols.contains(c) {
+                            od[c].clone()
+                        } else {
+                            null_like(&new_t.column[c])
+                        };
+                        (c.clone(), val)
+                    })
+                    .collect();
+                new_t.data.push(row);
+            }
+        }
+
+        Ok(new_t)
+    }
+
+    /// Write every row to `path` using the order-preserving [`encode_row`]
+    /// tuple format, so the table survives a process restart.
+    fn save(&self, path: &str) -> io::Result<()> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&(self.data.len() as u32).to_be_bytes());
+        for row in &self.data {
+            let encoded = encode_row(row, &self.order);
+            buf.extend_from_slice(&(encoded.len() as u32).to_be_bytes());
+            buf.extend_from_slice(&encoded);
+        }
+        std::fs::write(path, buf)
+    }
+
+    /// Read back a table previously written by [`Table::save`]. The schema
+    /// (`name`/`col`) must be supplied by the caller, matching `Table::new`.
+    fn open(path: &str, name: &str, col: Vec<(&str, Type)>) -> io::Result<Table> {
+        let mut table = Table::new(name, col);
+        let bytes = std::fs::read(path)?;
+        let count = u32::from_be_bytes(bytes[..4].try_into().unwrap()) as usize;
+        let mut pos = 4;
+        for _ in 0..count {
+            let len = u32::from_be_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+            pos += 4;
+            let row = decode_row(&bytes[pos..pos + len], &table.order, &table.column);
+            pos += len;
+            for col in &table.order.clone() {
+                let len = value_len(&row[col]);
+                if table.max_lens[col] < len {
+                    table.max_lens.insert(col.clone(), len);
+                }
+            }
+            table.data.push(row);
+        }
+        Ok(table)
+    }
+
+    /// Rows whose `col` value falls within `[lo, hi]`. Encodes and sorts
+    /// `col`'s values with the same order-preserving tuple encoding as
+    /// `save`/`open`, then binary-searches the matching span instead of
+    /// comparing every row against `lo`/`hi` individually. That sort is
+    /// redone on every call rather than cached or persisted, so this is
+    /// `O(n log n)` per call, not a free lookup against an on-disk index -
+    /// a real scalability win would need a maintained index.
+    fn range(&self, col: &str, lo: &Type, hi: &Type) -> Table {
+        let mut indexed: Vec<(Vec<u8>, usize)> = self.data.iter()
+            .enumerate()
+            .map(|(i, d)| (encode_type(&d[col]), i))
+            .collect();
+        indexed.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let lo_key = encode_type(lo);
+        let hi_key = encode_type(hi);
+        let start = indexed.partition_point(|(k, _)| k < &lo_key);
+        let end   = indexed.partition_point(|(k, _)| k <= &hi_key);
+
+        let mut new_t = self.clone();
+        new_t.data = indexed[start..end].iter().map(|(_, i)| self.data[*i].clone()).collect();
+        new_t
+    }
+
+    /// Partition rows by `keys` and compute `aggs` per group, e.g.
+    /// `t.group_by(&["category"], &[(AggFn::Count, "id"), (AggFn::Sum, "price")])`.
+    /// Groups are kept in a `BTreeMap` keyed on the group-key values so
+    /// they come out in sorted, deterministic order. The result's columns
+    /// are the key columns followed by synthesized aggregate names such as
+    /// `sum_price`.
+    fn group_by(&self, keys: &[&str], aggs: &[(AggFn, &str)]) -> Table {
+        let mut groups: BTreeMap<Vec<Type>, Vec<Accumulator>> = BTreeMap::new();
+        for row in &self.data {
+            let key: Vec<Type> = keys.iter().map(|k| row[*k].clone()).collect();
+            let accs = groups.entry(key).or_insert_with(|| vec![Accumulator::default(); aggs.len()]);
+            for (acc, (_, col)) in accs.iter_mut().zip(aggs.iter()) {
+                acc.add(&row[*col]);
+            }
+        }
+
+        let agg_names: Vec<String> = aggs.iter()
+            .map(|(f, col)| format!("{}_{}", agg_fn_name(f), col))
+            .collect();
+
+        let mut order: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+        let mut column: HashMap<String, Type> = HashMap::new();
+        for k in keys {
+            column.insert(k.to_string(), self.column[*k].clone());
+        }
+        for (name, (f, col)) in agg_names.iter().zip(aggs.iter()) {
+            let ty = match f {
+                AggFn::Count => Type::Int(None),
+                AggFn::Avg   => match &self.column[*col] {
+                    Type::Decimal(_) => Type::Decimal(None),
+                    _                => Type::Float(None),
+                },
+                _ => self.column[*col].clone(),
+            };
+            order.push(name.clone());
+            column.insert(name.clone(), ty);
+        }
+
+        let mut max_lens: HashMap<String, usize> = order.iter().map(|c| (c.clone(), c.len())).collect();
+        let mut data = Vec::new();
+        for (key, accs) in groups {
+            let mut row = HashMap::new();
+            for (k, v) in keys.iter().zip(key) {
+                row.insert(k.to_string(), v);
+            }
+            for (i, (f, col)) in aggs.iter().enumerate() {
+                let acc = &accs[i];
+                let val = match f {
+                    AggFn::Count => Type::Int(Some(acc.count as i32)),
+                    AggFn::Sum   => acc.sum.clone().unwrap_or_else(|| null_like(&self.column[*col])),
+                    AggFn::Min   => acc.min.clone().unwrap_or_else(|| null_like(&self.column[*col])),
+                    AggFn::Max   => acc.max.clone().unwrap_or_else(|| null_like(&self.column[*col])),
+                    AggFn::Avg   => match &acc.sum {
+                        Some(sum) if acc.count > 0 => finalize_avg(sum, acc.count),
+                        _ => null_like(&column[&agg_names[i]]),
+                    },
+                };
+                row.insert(agg_names[i].clone(), val);
+            }
+            for col in &order {
+                let len = value_len(&row[col]);
+                if max_lens[col] < len {
+                    max_lens.insert(col.clone(), len);
+                }
             }
+            data.push(row);
         }
 
+        Table { name: format!("{}_grouped", self.name), order, column, max_lens, data }
+    }
+
+    /// Sort rows by one or more `(column, direction)` keys, comparing
+    /// `Int` numerically and `Text` lexically, with NULLs sorting last on
+    /// `Asc` and first on `Desc`. The sort is stable and multi-key: ties
+    /// on an earlier key fall through to the next one. Returns a new
+    /// `Table` so callers can chain `.select()`/`.limit()` afterwards.
+    fn order_by(&self, keys: &[(&str, Order)]) -> Table {
+        let mut new_t = self.clone();
+        new_t.data.sort_by(|a, b| {
+            for (col, dir) in keys {
+                let ord = compare_nullable(&a[*col], &b[*col]);
+                let ord = match dir {
+                    Order::Asc  => ord,
+                    Order::Desc => ord.reverse(),
+                };
+                if ord != std::cmp::Ordering::Equal {
+                    return ord;
+                }
+            }
+            std::cmp::Ordering::Equal
+        });
+        new_t
+    }
+
+    /// Keep at most `n` rows, skipping the first `offset`.
+    fn limit(&self, n: usize, offset: usize) -> Table {
+        let mut new_t = self.clone();
+        new_t.data = self.data.iter().skip(offset).take(n).cloned().collect();
         new_t
     }
 
@@ -187,9 +939,18 @@ impl Table {
                         Type::Int(Some(val))  => {
                             print!("{:>width$}", val, width = self.max_lens[col]);
                         },
+                        Type::Float(Some(val)) => {
+                            print!("{:>width$}", val, width = self.max_lens[col]);
+                        },
+                        Type::Decimal(Some(val)) => {
+                            print!("{:>width$}", val, width = self.max_lens[col]);
+                        },
                         Type::Text(Some(text)) => {
                             print!("{:<width$}", text, width = self.max_lens[col]);
                         },
+                        Type::DateTime(Some(dt)) => {
+                            print!("{:<width$}", dt.to_rfc3339(), width = self.max_lens[col]);
+                        },
                         _ => {
                             print!("{:>width$}", "NULL", width = self.max_lens[col]);
                         },
@@ -220,6 +981,46 @@ fn i32_len(mut i: i32) -> usize {
     len
 }
 
+/// Display width of a value, used to keep `max_lens` wide enough for it.
+fn value_len(t: &Type) -> usize {
+    match t {
+        Type::Int(Some(i))       => i32_len(*i),
+        Type::Float(Some(f))     => f.to_string().len(),
+        Type::Decimal(Some(d))   => d.to_string().len(),
+        Type::Text(Some(s))      => s.len(),
+        Type::DateTime(Some(dt)) => dt.to_rfc3339().len(),
+        _ => 4, // null
+    }
+}
+
+fn tokenize_like(pattern: &str) -> Vec<LikeType> {
+    let mut tokens = vec![];
+    let pattern_chars = pattern.chars().collect::<Vec<char>>();
+    let mut idx = 0;
+    while idx < pattern_chars.len() {
+        match pattern_chars[idx] {
+            '_' => {
+                tokens.push(LikeType::Underscore);
+                idx += 1;
+            }
+            '%' => {
+                tokens.push(LikeType::Percent);
+                idx += 1;
+            }
+             _  => {
+                 let mut s = pattern_chars[idx].to_string();
+                 idx += 1;
+                 while idx < pattern_chars.len() && pattern_chars[idx] != '%' && pattern_chars[idx] != '_' {
+                     s.push(pattern_chars[idx]);
+                     idx += 1;
+                 }
+                 tokens.push(LikeType::Str(s));
+             }
+        }
+    }
+    tokens
+}
+
 fn like(mut pattern: &[LikeType], mut target: &str) -> bool {
     loop {
         if target.is_empty() {
@@ -276,20 +1077,45 @@ fn main() {
 
     let mut table2 = Table::new("table2",
         vec![ ("id", Type::Int(None)),
-              ("date", Type::Text(None)), ]);
+              ("date", Type::DateTime(None)), ]);
 
     table2.insert(vec![("id", Type::Int(Some(1))),
-                       ("date", Type::Text(Some("2019/12/20".to_owned())))]);
+                       ("date", Type::DateTime(Some(parse_datetime("2019/12/20"))))]);
     table2.insert(vec![("id", Type::Int(Some(2))),
-                       ("date", Type::Text(Some("2019/12/21".to_owned())))]);
+                       ("date", Type::DateTime(Some(parse_datetime("2019/12/21"))))]);
     table2.insert(vec![("id", Type::Int(Some(3))),
-                       ("date", Type::Text(Some("2019/12/22".to_owned())))]);
+                       ("date", Type::DateTime(Some(parse_datetime("2019/12/22"))))]);
     table2.insert(vec![("id", Type::Int(Some(4))),
-                       ("date", Type::Text(Some("2019/12/23".to_owned())))]);
+                       ("date", Type::DateTime(Some(parse_datetime("2019/12/23"))))]);
     table2.insert(vec![("id", Type::Int(Some(8))),
-                       ("date", Type::Text(Some("2019/12/27".to_owned())))]);
+                       ("date", Type::DateTime(Some(parse_datetime("2019/12/27"))))]);
     table2.insert(vec![("id", Type::Int(Some(13))),
-                       ("date", Type::Text(Some("2020/01/01".to_owned())))]);
+                       ("date", Type::DateTime(Some(parse_datetime("2020/01/01"))))]);
+
+    let mut table3 = Table::new("table3",
+        vec![ ("id",     Type::Int(None)),
+              ("price",  Type::Decimal(None)),
+              ("weight", Type::Float(None)), ]);
+
+    table3.insert(vec![("id",     Type::Int(Some(1))),
+                       ("price",  Type::Decimal(Some("9.99".parse().unwrap()))),
+                       ("weight", Type::Float(Some(0.15)))]);
+    table3.insert(vec![("id",     Type::Int(Some(2))),
+                       ("price",  Type::Decimal(Some("12.50".parse().unwrap()))),
+                       ("weight", Type::Float(Some(0.32)))]);
+    table3.insert(vec![("id",     Type::Int(Some(3))),
+                       ("price",  Type::Decimal(Some("5.00".parse().unwrap()))),
+                       ("weight", Type::Float(Some(0.08)))]);
+
+    let mut orders = Table::new("orders",
+        vec![ ("customer", Type::Text(None)),
+              ("amount",   Type::Int(None)), ]);
+
+    orders.insert(vec![("customer", Type::Text(Some("alice".to_owned()))), ("amount", Type::Int(Some(30)))]);
+    orders.insert(vec![("customer", Type::Text(Some("alice".to_owned()))), ("amount", Type::Int(Some(70)))]);
+    orders.insert(vec![("customer", Type::Text(Some("bob".to_owned()))),   ("amount", Type::Int(Some(20)))]);
+    orders.insert(vec![("customer", Type::Text(Some("bob".to_owned()))),   ("amount", Type::Int(None))]);
+    orders.insert(vec![("customer", Type::Text(Some("carol".to_owned()))), ("amount", Type::Int(Some(100)))]);
 
     println!("\n====[ table1 ALL ]====");
     table1.display();
@@ -298,23 +1124,73 @@ fn main() {
     table1.select(&["name"]).display();
     table1.select(&["name", "price"]).display();
 
-    println!("\n====[ table1 WHERE < ]====");
-    table1.less_than("id", 10).display();
-    table1.less_than("price", 250).display();
+    println!("\n====[ table1 WHERE ]====");
+    table1.filter("id < 10").display();
+    table1.filter("price < 250").display();
+    table1.filter("price >= 100 AND name LIKE '%ri%' OR id < 3").display();
 
     println!("\n====[ table2 ALL ]====");
     table2.display();
 
+    println!("\n====[ table2 WHERE date < '2020/01/01' ]====");
+    table2.filter("date < '2020/01/01'").display();
+
+    println!("\n====[ table3 ALL ]====");
+    table3.display();
+
+    println!("\n====[ table3 WHERE price > 9.99 ]====");
+    table3.filter("price > 9.99").display();
+
+    println!("\n====[ orders ALL ]====");
+    orders.display();
+
+    println!("\n====[ orders GROUP BY customer ]====");
+    orders.group_by(&["customer"],
+        &[(AggFn::Count, "amount"), (AggFn::Sum, "amount"), (AggFn::Min, "amount"),
+          (AggFn::Max, "amount"), (AggFn::Avg, "amount")]).display();
+
     println!("\n====[ table1:table2 LEFT JOIN ]====");
-    table1.left_join(&table2, "id").display();
+    table1.join(&table2, "id", JoinKind::Left).unwrap().display();
 
     println!("\n====[ table1:table2 LEFT JOIN => SELECT ]====");
-    table1.left_join(&table2, "id").select(&["name", "date"]).display();
+    table1.join(&table2, "id", JoinKind::Left).unwrap().select(&["name", "date"]).display();
+
+    println!("\n====[ table1:table2 INNER JOIN ]====");
+    table1.join(&table2, "id", JoinKind::Inner).unwrap().display();
+
+    println!("\n====[ table1:table2 RIGHT JOIN ]====");
+    table1.join(&table2, "id", JoinKind::Right).unwrap().display();
+
+    println!("\n====[ table1:table2 FULL OUTER JOIN ]====");
+    table1.join(&table2, "id", JoinKind::Full).unwrap().display();
+
+    println!("\n====[ table1:table2 JOIN on missing key ]====");
+    match table1.join(&table2, "no_such_column", JoinKind::Inner) {
+        Ok(t)  => t.display(),
+        Err(e) => println!("error: {}", e),
+    }
 
     println!("\n====[ table1 WHERE LIKE ]====");
-    table1.like("name", "apple").display();
-    table1.like("name", "______").display();
-    table1.like("name", "%s").display();
-    table1.like("name", "%ri%").display();
+    table1.filter("name LIKE 'apple'").display();
+    table1.filter("name LIKE '______'").display();
+    table1.filter("name LIKE '%s'").display();
+    table1.filter("name LIKE '%ri%'").display();
+
+    println!("\n====[ table1 RANGE ]====");
+    table1.range("price", &Type::Int(Some(100)), &Type::Int(Some(1024))).display();
+
+    println!("\n====[ table1:table2 LEFT JOIN => ORDER BY price DESC LIMIT 3 ]====");
+    table1.join(&table2, "id", JoinKind::Left).unwrap().order_by(&[("price", Order::Desc)]).limit(3, 0).display();
+
+    println!("\n====[ table1 ORDER BY price ASC, name ASC ]====");
+    table1.order_by(&[("price", Order::Asc), ("name", Order::Asc)]).display();
+
+    println!("\n====[ table1 SAVE/OPEN ]====");
+    table1.save("table1.toydb").expect("failed to save table1");
+    let reopened = Table::open("table1.toydb", "table1",
+        vec![ ("id",    Type::Int(None)),
+              ("name",  Type::Text(None)),
+              ("price", Type::Int(None)), ]).expect("failed to open table1");
+    reopened.display();
 }
 